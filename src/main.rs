@@ -1,20 +1,87 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_s3 as s3;
+use futures::stream::{self, StreamExt};
 use s3::model::{
     BucketLocationConstraint, CompletedMultipartUpload, CompletedPart, CreateBucketConfiguration,
 };
-use s3::output::{CreateMultipartUploadOutput, UploadPartOutput};
-use s3::{ByteStream, Client, Error, Region};
+use s3::output::CreateMultipartUploadOutput;
+use s3::{ByteStream, Client, Credentials, Error, Region};
 
 use structopt::StructOpt;
+use tokio::io::{self, AsyncWriteExt};
+
+/// S3 rejects multipart parts smaller than 5 MiB (except the final part) and
+/// larger than 5 GiB.
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Parses a `key=value` pair from `--meta`, as used for object metadata.
+fn parse_meta_pair(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected `key=value`, got `{}`", s)),
+    }
+}
+
+/// Guesses a MIME type from a file's extension, falling back to
+/// `application/octet-stream` when the extension is unknown.
+fn guess_content_type(obj: &path::Path) -> String {
+    let extension = obj
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "js" => "application/javascript",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "mp4" => "video/mp4",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
 
 #[derive(StructOpt, Debug)]
 struct S3Command {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// Talk to an S3-compatible server (e.g. MinIO) instead of AWS.
+    #[structopt(long)]
+    endpoint_url: Option<String>,
+
+    /// Use path-style bucket addressing (required by most non-AWS servers).
+    #[structopt(long)]
+    path_style: bool,
+
+    #[structopt(long, requires = "secret-key")]
+    access_key: Option<String>,
+
+    #[structopt(long, requires = "access-key")]
+    secret_key: Option<String>,
+
     #[structopt(subcommand)]
     operation: S3Operation,
 }
@@ -39,6 +106,12 @@ enum S3Operation {
     ListBuckets,
     ListObjects {
         bucket: String,
+        /// Only list keys beginning with this prefix.
+        #[structopt(long)]
+        prefix: Option<String>,
+        /// Stop after this many keys total, across all pages.
+        #[structopt(long)]
+        max_keys: Option<i32>,
     },
 
     CreateObject {
@@ -46,6 +119,12 @@ enum S3Operation {
         key: String,
         #[structopt(parse(from_os_str))]
         obj: path::PathBuf,
+        /// Overrides the content type guessed from the file extension.
+        #[structopt(long)]
+        content_type: Option<String>,
+        /// User metadata, repeatable, e.g. `--meta author=jane --meta env=prod`.
+        #[structopt(long = "meta", parse(try_from_str = parse_meta_pair))]
+        meta: Vec<(String, String)>,
     },
 
     DeleteObject {
@@ -56,15 +135,51 @@ enum S3Operation {
     GetObject {
         bucket: String,
         key: String,
+        /// Destination file, or `-` for stdout.
+        #[structopt(parse(from_os_str), default_value = "-")]
+        out: path::PathBuf,
+        /// Byte range to fetch, as `start-end` (inclusive).
+        #[structopt(long)]
+        range: Option<String>,
     },
 
     MultipartUpload {
         bucket: String,
         key: String,
+        #[structopt(parse(from_os_str))]
+        obj: path::PathBuf,
+        /// Bytes per part, clamped to the S3-valid range 5 MiB..=5 GiB.
+        #[structopt(long)]
+        part_size: Option<u64>,
+        /// Maximum number of parts uploaded in parallel.
+        #[structopt(long, default_value = "4")]
+        concurrency: usize,
+        /// Overrides the content type guessed from the file extension.
+        #[structopt(long)]
+        content_type: Option<String>,
+        /// User metadata, repeatable, e.g. `--meta author=jane --meta env=prod`.
+        #[structopt(long = "meta", parse(try_from_str = parse_meta_pair))]
+        meta: Vec<(String, String)>,
     },
 
     ListMultiparts {
         bucket: String,
+        /// Only list uploads for keys beginning with this prefix.
+        #[structopt(long)]
+        prefix: Option<String>,
+    },
+
+    AbortMultipart {
+        bucket: String,
+        key: String,
+        upload_id: String,
+    },
+
+    /// Abort every multipart upload in `bucket` initiated more than
+    /// `older_than_days` days ago, reclaiming storage from abandoned uploads.
+    CleanupMultiparts {
+        bucket: String,
+        older_than_days: i64,
     },
 }
 
@@ -93,16 +208,28 @@ async fn execute_operation(client: Client, oper: S3Command) {
 
             println!("Resp: {:#?}", resp);
         }
-        S3Operation::CreateObject { bucket, key, obj } => {
+        S3Operation::CreateObject {
+            bucket,
+            key,
+            obj,
+            content_type,
+            meta,
+        } => {
             let obj_stream = ByteStream::from_path(obj).await.unwrap();
+            let content_type = content_type.clone().unwrap_or_else(|| guess_content_type(obj));
 
-            let create_resp = client
+            let mut req = client
                 .put_object()
                 .bucket(bucket)
                 .body(obj_stream)
                 .key(key)
-                .send()
-                .await;
+                .content_type(content_type);
+
+            for (meta_key, meta_value) in meta {
+                req = req.metadata(meta_key, meta_value);
+            }
+
+            let create_resp = req.send().await;
 
             println!("Resp: {:?}", create_resp);
         }
@@ -112,22 +239,13 @@ async fn execute_operation(client: Client, oper: S3Command) {
             println!("Resp: {:?}", delete_resp);
         }
 
-        S3Operation::GetObject { bucket, key } => {
-            let requested_object = client.get_object().bucket(bucket).key(key).send().await;
-
-            match requested_object {
-                Err(err) => println!("Error: {}", err),
-                Ok(resp_obj) => resp_obj
-                    .body
-                    .collect()
-                    .await
-                    .map(|byte_stream| {
-                        let st = String::from_utf8(byte_stream.into_bytes().to_vec());
-                        println!("Object Recv: {:#?}", st);
-                    })
-                    .map_err(|err| println!("Streaming Error: {}", err))
-                    .unwrap_or(()),
-            }
+        S3Operation::GetObject {
+            bucket,
+            key,
+            out,
+            range,
+        } => {
+            handle_get_object(client, bucket, key, out, range).await;
         }
 
         S3Operation::ListBuckets => {
@@ -154,30 +272,82 @@ async fn execute_operation(client: Client, oper: S3Command) {
             }
         }
 
-        S3Operation::ListObjects { bucket } => {
-            let all_objects = client.list_objects().bucket(bucket).send().await;
+        S3Operation::ListObjects {
+            bucket,
+            prefix,
+            max_keys,
+        } => {
+            println!("Objects in Bucket: {}:", bucket);
 
-            match all_objects {
-                Err(err) => {
-                    println!("Err {}", err);
+            let mut continuation_token = None;
+            let mut total = 0;
+
+            loop {
+                let mut req = client.list_objects_v2().bucket(bucket);
+                if let Some(prefix) = prefix {
+                    req = req.prefix(prefix);
                 }
-                Ok(objects) => {
-                    println!("Objects in Bucket: {}:", bucket);
-
-                    match objects.contents {
-                        None => println!("No Objects in the bucket"),
-                        Some(objects) => {
-                            for (idx, object) in objects.iter().enumerate() {
-                                println!("{}: {:?}", idx, object);
+                if let Some(token) = &continuation_token {
+                    req = req.continuation_token(token);
+                }
+
+                let resp = match req.send().await {
+                    Ok(resp) => resp,
+                    Err(err) => {
+                        println!("Err {}", err);
+                        break;
+                    }
+                };
+
+                if let Some(objects) = resp.contents {
+                    for object in objects {
+                        if let Some(max_keys) = max_keys {
+                            if total >= *max_keys {
+                                break;
                             }
                         }
+                        println!("{}: {:?}", total, object);
+                        total += 1;
                     }
                 }
+
+                let reached_cap = max_keys.map_or(false, |max_keys| total >= max_keys);
+                if reached_cap {
+                    break;
+                }
+
+                if resp.is_truncated {
+                    continuation_token = resp.next_continuation_token;
+                } else {
+                    break;
+                }
+            }
+
+            if total == 0 {
+                println!("No Objects in the bucket");
             }
         }
 
-        S3Operation::MultipartUpload { bucket, key } => {
-            handle_multipart(client, bucket, key).await;
+        S3Operation::MultipartUpload {
+            bucket,
+            key,
+            obj,
+            part_size,
+            concurrency,
+            content_type,
+            meta,
+        } => {
+            handle_multipart_upload(
+                client,
+                bucket,
+                key,
+                obj,
+                *part_size,
+                *concurrency,
+                content_type.clone().unwrap_or_else(|| guess_content_type(obj)),
+                meta,
+            )
+            .await;
         }
 
         S3Operation::ExistBucket { bucket } => {
@@ -185,9 +355,61 @@ async fn execute_operation(client: Client, oper: S3Command) {
             println!("{:?}", resp);
         }
 
-        S3Operation::ListMultiparts { bucket } => {
-            let resp = client.list_multipart_uploads().bucket(bucket).send().await;
-            println!("{:?}", resp.unwrap().uploads);
+        S3Operation::ListMultiparts { bucket, prefix } => {
+            let mut key_marker = None;
+            let mut upload_id_marker = None;
+
+            loop {
+                let mut req = client.list_multipart_uploads().bucket(bucket);
+                if let Some(prefix) = prefix {
+                    req = req.prefix(prefix);
+                }
+                if let Some(marker) = &key_marker {
+                    req = req.key_marker(marker);
+                }
+                if let Some(marker) = &upload_id_marker {
+                    req = req.upload_id_marker(marker);
+                }
+
+                let resp = match req.send().await {
+                    Ok(resp) => resp,
+                    Err(err) => {
+                        println!("Err: {}", err);
+                        break;
+                    }
+                };
+
+                println!("{:?}", resp.uploads);
+
+                if resp.is_truncated {
+                    key_marker = resp.next_key_marker;
+                    upload_id_marker = resp.next_upload_id_marker;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        S3Operation::AbortMultipart {
+            bucket,
+            key,
+            upload_id,
+        } => {
+            let resp = client
+                .abort_multipart_upload()
+                .bucket(bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .send()
+                .await;
+            println!("Resp: {:?}", resp);
+        }
+
+        S3Operation::CleanupMultiparts {
+            bucket,
+            older_than_days,
+        } => {
+            handle_cleanup_multiparts(client, bucket, *older_than_days).await;
         }
         _ => {
             println!("Not Yet Implemented");
@@ -195,109 +417,329 @@ async fn execute_operation(client: Client, oper: S3Command) {
     }
 }
 
-async fn handle_multipart(client: Client, bucket: &str, key: &str) {
-    // Takes file part names to upload the objects in sequence.
+/// Clamp a requested part size into the S3-valid range, defaulting to
+/// `DEFAULT_PART_SIZE` when unset.
+fn clamp_part_size(requested: Option<u64>) -> u64 {
+    requested
+        .unwrap_or(DEFAULT_PART_SIZE)
+        .clamp(MIN_PART_SIZE, MAX_PART_SIZE)
+}
 
-    // It's 3 Step process.
-    // Step 1: Initiate a Multipart Thing.
-    let initiation = client
-        .create_multipart_upload()
-        .bucket(bucket)
-        .key(key.clone())
-        .send()
-        .await;
+/// Splits `obj` into `part_size` chunks and uploads them with at most
+/// `concurrency` parts in flight at once. Aborts the upload on any part
+/// failure so no orphaned parts are left behind.
+async fn handle_multipart_upload(
+    client: Client,
+    bucket: &str,
+    key: &str,
+    obj: &path::Path,
+    part_size: Option<u64>,
+    concurrency: usize,
+    content_type: String,
+    meta: &[(String, String)],
+) {
+    let file = match File::open(obj) {
+        Ok(file) => file,
+        Err(err) => {
+            println!("Err: failed to open {:?}: {}", obj, err);
+            return;
+        }
+    };
 
-    if initiation.is_err() {
-        println!("Err: {}", initiation.unwrap_err());
+    let file_len = match file.metadata() {
+        Ok(metadata) => metadata.len(),
+        Err(err) => {
+            println!("Err: failed to stat {:?}: {}", obj, err);
+            return;
+        }
+    };
+
+    if file_len == 0 {
+        println!("0-byte file; using a plain PutObject instead of a multipart upload.");
+        let mut req = client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .body(ByteStream::from(Vec::new()))
+            .content_type(content_type);
+        for (meta_key, meta_value) in meta {
+            req = req.metadata(meta_key, meta_value);
+        }
+        println!("Resp: {:?}", req.send().await);
         return;
     }
 
-    let CreateMultipartUploadOutput {
-        bucket, upload_id, ..
-    } = initiation.unwrap();
-
-    println!("1. Initiated MultiPart Upload.");
+    let concurrency = concurrency.max(1);
+    let part_size = clamp_part_size(part_size);
+    let num_parts = ((file_len + part_size - 1) / part_size).max(1);
 
-    println!("2. Sequencially enter the file names: ");
-
-    let stdin = std::io::stdin();
-    let mut buf = String::new();
-    let mut part_number = 0;
-    let mut completed_parts = Vec::new();
+    // Step 1: Initiate the multipart upload.
+    let mut initiate_req = client
+        .create_multipart_upload()
+        .bucket(bucket)
+        .key(key)
+        .content_type(content_type);
+    for (meta_key, meta_value) in meta {
+        initiate_req = initiate_req.metadata(meta_key, meta_value);
+    }
+    let initiation = initiate_req.send().await;
 
-    // Step2: Upload all the parts one by one.
-    // Aborting commands isn't allowed right now.
-    'outer: loop {
-        if let Ok(n) = stdin.read_line(&mut buf) {
-            // Nothing is read => ctrl+d is hit maybe..
-            if n == 0 {
-                buf = String::from("END");
-            }
+    let CreateMultipartUploadOutput { upload_id, .. } = match initiation {
+        Ok(output) => output,
+        Err(err) => {
+            println!("Err: {}", err);
+            return;
+        }
+    };
+    let upload_id = upload_id.expect("S3 always returns an upload_id");
+
+    println!(
+        "1. Initiated MultiPart Upload: {} parts of up to {} bytes each.",
+        num_parts, part_size
+    );
+
+    // Step 2: Upload all the parts, with at most `concurrency` in flight.
+    let uploads = (0..num_parts).map(|idx| {
+        let client = client.clone();
+        let mut part_file = file.try_clone().expect("failed to clone file handle");
+        let upload_id = upload_id.clone();
+        let part_number = (idx + 1) as i32;
+        let offset = idx * part_size;
+        let this_part_size = part_size.min(file_len - offset);
+
+        async move {
+            let mut buf = vec![0u8; this_part_size as usize];
+            part_file
+                .seek(SeekFrom::Start(offset))
+                .and_then(|_| part_file.read_exact(&mut buf))
+                .map_err(|err| format!("part {}: failed to read chunk: {}", part_number, err))?;
 
-            if buf == "END" {
-                client
-                    .complete_multipart_upload()
-                    .upload_id(upload_id.clone().unwrap())
-                    .bucket(bucket.clone().unwrap())
-                    .key(key)
-                    .multipart_upload(
-                        CompletedMultipartUpload::builder()
-                            .set_parts(Some(completed_parts))
-                            .build(),
-                    )
-                    .send()
-                    .await
-                    .unwrap();
-
-                println!("Completed Mutlipart Upload.");
-                break 'outer;
-            }
-            buf.pop();
-            let body = ByteStream::from_path(buf.clone()).await.unwrap();
-            part_number += 1;
             let resp = client
                 .upload_part()
-                .body(body)
-                .bucket(bucket.clone().unwrap())
-                .key(key.clone())
+                .bucket(bucket)
+                .key(key)
+                .upload_id(&upload_id)
                 .part_number(part_number)
-                .upload_id(upload_id.clone().unwrap())
+                .body(ByteStream::from(buf))
                 .send()
                 .await
-                .unwrap();
+                .map_err(|err| format!("part {}: {}", part_number, err))?;
+
+            let e_tag = resp
+                .e_tag
+                .ok_or_else(|| format!("part {}: response missing e_tag", part_number))?;
 
-            completed_parts.push(
+            Ok::<_, String>(
                 CompletedPart::builder()
-                    .e_tag(resp.e_tag.unwrap())
+                    .e_tag(e_tag)
                     .part_number(part_number)
                     .build(),
-            );
-        } else {
-            // End the object updation.
-            client
-                .complete_multipart_upload()
-                .upload_id(upload_id.clone().unwrap())
-                .bucket(bucket.clone().unwrap())
+            )
+        }
+    });
+
+    let mut results = stream::iter(uploads).buffer_unordered(concurrency);
+    let mut completed_parts = Vec::with_capacity(num_parts as usize);
+    let mut first_error = None;
+
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(part) => completed_parts.push(part),
+            Err(err) if first_error.is_none() => first_error = Some(err),
+            Err(_) => {}
+        }
+    }
+
+    if let Some(err) = first_error {
+        println!("Err: {}", err);
+        println!("Aborting multipart upload {}...", upload_id);
+        let abort = client
+            .abort_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(&upload_id)
+            .send()
+            .await;
+        println!("Abort Resp: {:?}", abort);
+        return;
+    }
+
+    completed_parts.sort_by_key(|part| part.part_number);
+
+    println!("2. Uploaded {} parts.", completed_parts.len());
+
+    // Step 3: Complete the multipart upload.
+    let complete = client
+        .complete_multipart_upload()
+        .upload_id(&upload_id)
+        .bucket(bucket)
+        .key(key)
+        .multipart_upload(
+            CompletedMultipartUpload::builder()
+                .set_parts(Some(completed_parts))
+                .build(),
+        )
+        .send()
+        .await;
+
+    match complete {
+        Ok(resp) => println!("3. Completed Multipart Upload: {:?}", resp),
+        Err(err) => println!("Err: {}", err),
+    }
+}
+
+/// Streams `GetObject`'s body to `out` (or stdout, when `out` is `-`) in
+/// chunks so memory stays bounded regardless of object size. When `range` is
+/// set, only that byte range is fetched.
+async fn handle_get_object(
+    client: Client,
+    bucket: &str,
+    key: &str,
+    out: &path::Path,
+    range: &Option<String>,
+) {
+    let mut req = client.get_object().bucket(bucket).key(key);
+    if let Some(range) = range {
+        req = req.range(format!("bytes={}", range));
+    }
+
+    let mut resp = match req.send().await {
+        Ok(resp) => resp,
+        Err(err) => {
+            println!("Error: {}", err);
+            return;
+        }
+    };
+
+    let to_stdout = out.as_os_str() == "-";
+    let mut file = if to_stdout {
+        None
+    } else {
+        match tokio::fs::File::create(out).await {
+            Ok(file) => Some(file),
+            Err(err) => {
+                println!("Err: failed to create {:?}: {}", out, err);
+                return;
+            }
+        }
+    };
+
+    loop {
+        match resp.body.try_next().await {
+            Ok(Some(chunk)) => {
+                let write_result = match &mut file {
+                    Some(file) => file.write_all(&chunk).await,
+                    None => io::stdout().write_all(&chunk).await,
+                };
+                if let Err(err) = write_result {
+                    println!("Write Error: {}", err);
+                    return;
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                println!("Streaming Error: {}", err);
+                return;
+            }
+        }
+    }
+
+    let flush_result = match &mut file {
+        Some(file) => file.flush().await,
+        None => io::stdout().flush().await,
+    };
+    if let Err(err) = flush_result {
+        println!("Write Error: {}", err);
+        return;
+    }
+
+    if !to_stdout {
+        println!("Wrote object to {:?}", out);
+    }
+}
+
+/// Pages through every in-progress multipart upload in `bucket` and aborts
+/// the ones initiated more than `older_than_days` days ago.
+async fn handle_cleanup_multiparts(client: Client, bucket: &str, older_than_days: i64) {
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before the epoch")
+        .as_secs() as i64;
+    let threshold_secs = now_secs - older_than_days * 24 * 60 * 60;
+
+    let mut key_marker = None;
+    let mut upload_id_marker = None;
+    let mut aborted = Vec::new();
+
+    loop {
+        let mut req = client.list_multipart_uploads().bucket(bucket);
+        if let Some(marker) = &key_marker {
+            req = req.key_marker(marker);
+        }
+        if let Some(marker) = &upload_id_marker {
+            req = req.upload_id_marker(marker);
+        }
+
+        let resp = match req.send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                println!("Err: {}", err);
+                return;
+            }
+        };
+
+        for upload in resp.uploads.unwrap_or_default() {
+            let (key, upload_id) = match (&upload.key, &upload.upload_id) {
+                (Some(key), Some(upload_id)) => (key, upload_id),
+                _ => continue,
+            };
+            let is_stale = upload
+                .initiated
+                .map(|initiated| initiated.secs() < threshold_secs)
+                .unwrap_or(false);
+            if !is_stale {
+                continue;
+            }
+
+            let abort = client
+                .abort_multipart_upload()
+                .bucket(bucket)
                 .key(key)
-                .multipart_upload(
-                    CompletedMultipartUpload::builder()
-                        .set_parts(Some(completed_parts))
-                        .build(),
-                )
+                .upload_id(upload_id)
                 .send()
-                .await
-                .unwrap();
+                .await;
 
-            println!("Completed Mutlipart Upload.");
+            match abort {
+                Ok(_) => aborted.push(upload_id.clone()),
+                Err(err) => println!("Err: failed to abort {}: {}", upload_id, err),
+            }
+        }
 
-            break 'outer;
+        if resp.is_truncated {
+            key_marker = resp.next_key_marker;
+            upload_id_marker = resp.next_upload_id_marker;
+        } else {
+            break;
         }
     }
+
+    println!(
+        "Aborted {} stale upload(s): {:?}",
+        aborted.len(),
+        aborted
+    );
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    let S3Command { region, operation } = S3Command::from_args();
+    let S3Command {
+        region,
+        endpoint_url,
+        path_style,
+        access_key,
+        secret_key,
+        operation,
+    } = S3Command::from_args();
 
     // println!("Executing {:?}....", opt);
 
@@ -306,9 +748,36 @@ async fn main() -> Result<(), Error> {
         .or_else(Region::new("us-west-2"));
 
     let shared_config = aws_config::from_env().region(region_provider).load().await;
-    let client = Client::new(&shared_config);
+    let mut config_builder = s3::config::Builder::from(&shared_config).force_path_style(path_style);
+
+    if let Some(endpoint_url) = &endpoint_url {
+        config_builder = config_builder.endpoint_url(endpoint_url);
+    }
+
+    if let (Some(access_key), Some(secret_key)) = (&access_key, &secret_key) {
+        config_builder = config_builder.credentials_provider(Credentials::new(
+            access_key,
+            secret_key,
+            None,
+            None,
+            "s3cli-static",
+        ));
+    }
 
-    execute_operation(client, S3Command { region, operation }).await;
+    let client = Client::from_conf(config_builder.build());
+
+    execute_operation(
+        client,
+        S3Command {
+            region,
+            endpoint_url,
+            path_style,
+            access_key,
+            secret_key,
+            operation,
+        },
+    )
+    .await;
 
     Ok(())
 }